@@ -0,0 +1,21 @@
+//! Feeds arbitrary input to the lexer; it should never panic, and every input should
+//! end in exactly one `EOF` with spans that can be used to slice the source.
+//!
+//! Run with `cargo +nightly fuzz run lexer -- -timeout=5` from the repository root,
+//! so inputs that make the lexer loop forever are reported too.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use monkeylang::{Lexer, TokenType};
+
+fuzz_target!(|input: &str| {
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.read();
+
+    assert_eq!(tokens.last().map(|token| &token.t_type), Some(&TokenType::EOF));
+    assert_eq!(tokens.iter().filter(|token| token.t_type == TokenType::EOF).count(), 1);
+
+    for token in &tokens {
+        assert!(input.get(token.span.start..token.span.end).is_some());
+    }
+});
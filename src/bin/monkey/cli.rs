@@ -0,0 +1,481 @@
+use std::process::ExitCode;
+
+/// Describes how to invoke the `monkey` binary.
+pub const USAGE: &str = "\
+Usage: monkey <command> [options]
+       monkey -e <source>
+       monkey -
+
+Commands:
+  run <files>...  Run a Monkey program, one file after another.
+  repl            Start an interactive session.
+  tokens <file>   Print the tokens of a Monkey program, like `run --emit tokens`.
+  tokens --diff <before> <after>
+                  Show how the tokens of two Monkey programs differ.
+  highlight <file>
+                  Print a Monkey program with syntax highlighting.
+  help            Show this message.
+
+Options:
+  -e, --eval <source>  Run the given source instead of a file.
+  --format <format>    Print diagnostics as `text` (the default) or `json`.
+  --time               Report how long each phase of the pipeline took.
+  --emit <stage>       Print what a stage of the pipeline produced; only `tokens` so far.
+  -v, --verbose        Trace each step of the pipeline; repeat (-vv) to trace every token.
+  -                    Read the program from stdin; also accepted as a <file>.
+  -h, --help           Show this message.
+  -V, --version        Show the version of monkey.
+
+Exit status:
+  0   Success.
+  1   `tokens --diff` found differences.
+  2   The command line couldn't be parsed.
+  65  The program has syntax errors.
+  66  The program couldn't be read.
+  74  Reading or writing the terminal or a socket failed.
+
+Run options:
+  --watch             Run the program again whenever its file changes.
+
+Highlight options:
+  --html              Print HTML instead of terminal colors.
+
+Repl options:
+  --no-color          Don't highlight results.
+  --listen <address>  Serve the REPL over TCP, e.g. 127.0.0.1:7007.";
+
+/// The file name which stands for standard input.
+pub const STDIN: &str = "-";
+
+/// A parsed invocation of the `monkey` binary.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Help,
+    Version,
+    Eval {
+        source: String,
+    },
+    Repl {
+        no_color: bool,
+        listen: Option<String>,
+    },
+    /// Runs each file in order.
+    Run {
+        files: Vec<String>,
+        watch: bool,
+    },
+    Tokens {
+        file: String,
+    },
+    Highlight {
+        file: String,
+        html: bool,
+    },
+    /// Compares the tokens of two files.
+    Diff {
+        before: String,
+        after: String,
+    },
+}
+
+/// Why a command failed, mapped onto the exit status of the process.
+/// The codes follow BSD's `sysexits.h` where one fits.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Failure {
+    /// Not an error as such: the files compared by `tokens --diff` differ, like `diff(1)`.
+    Different = 1,
+    Usage = 2,
+    Syntax = 65,
+    NoInput = 66,
+    Io = 74,
+}
+
+impl From<Failure> for ExitCode {
+    fn from(failure: Failure) -> Self {
+        ExitCode::from(failure as u8)
+    }
+}
+
+/// How diagnostics are printed.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    /// One JSON object per diagnostic, each on its own line.
+    Json,
+}
+
+/// A stage of the pipeline whose output can be printed with `--emit`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Emit {
+    Tokens,
+}
+
+/// Options which apply to every command.
+#[derive(Debug, PartialEq, Default)]
+pub struct Options {
+    pub format: Format,
+    /// Whether to report the timings of the pipeline's phases on stderr.
+    pub time: bool,
+    /// How much of the pipeline to trace on stderr; each `-v` adds a level.
+    pub verbosity: u8,
+    /// Which stage's output to print; nothing is printed for a program without errors otherwise.
+    pub emit: Option<Emit>,
+}
+
+/// Options whose next argument is their value, which is never read as a flag,
+/// so `monkey -e '-v'` runs `-v` rather than turning on tracing.
+const TAKES_VALUE: [&str; 3] = ["-e", "--eval", "--listen"];
+
+/// Gets the arguments of `args` which could be flags, skipping the values of [`TAKES_VALUE`] options.
+fn flags(args: &[String]) -> Vec<&str> {
+    let mut flags = vec![];
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        flags.push(arg.as_str());
+
+        if TAKES_VALUE.contains(&arg.as_str()) {
+            args.next();
+        }
+    }
+
+    flags
+}
+
+/// Takes the options which apply to every command out of `args`.
+/// Returns the options and the arguments which are left for the command.
+pub fn parse_options(args: &[String]) -> Result<(Options, Vec<String>), String> {
+    let mut options = Options::default();
+    let mut rest = vec![];
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                options.format = match args.next().map(String::as_str) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some(other) => return Err(format!("unknown format `{}`", other)),
+                    None => return Err(String::from("`--format` needs `text` or `json`")),
+                }
+            }
+            "--emit" => {
+                options.emit = match args.next().map(String::as_str) {
+                    Some("tokens") => Some(Emit::Tokens),
+                    Some(stage @ ("ast" | "bytecode" | "result")) => {
+                        return Err(format!(
+                            "`--emit {}` is not available yet; only `tokens` is",
+                            stage
+                        ))
+                    }
+                    Some(other) => return Err(format!("unknown stage `{}`", other)),
+                    None => return Err(String::from("`--emit` needs a stage, like `tokens`")),
+                }
+            }
+            "--time" => options.time = true,
+            "-v" | "--verbose" => options.verbosity += 1,
+            "-vv" => options.verbosity += 2,
+            value_option if TAKES_VALUE.contains(&value_option) => {
+                rest.push(arg.clone());
+                rest.extend(args.next().cloned());
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+
+    Ok((options, rest))
+}
+
+/// Parses the command line arguments, excluding the program name.
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let flags = flags(args);
+
+    if args.first().is_some_and(|arg| arg == "help")
+        || flags.iter().any(|flag| *flag == "-h" || *flag == "--help")
+    {
+        return Ok(Command::Help);
+    }
+
+    if flags
+        .iter()
+        .any(|flag| *flag == "-V" || *flag == "--version")
+    {
+        return Ok(Command::Version);
+    }
+
+    let (name, rest) = match args.split_first() {
+        Some((name, rest)) => (name.as_str(), rest),
+        None => return Err(String::from("no command given")),
+    };
+
+    match name {
+        "-e" | "--eval" => match rest {
+            [source] => Ok(Command::Eval {
+                source: source.clone(),
+            }),
+            [] => Err(format!("`{}` needs source to run", name)),
+            _ => Err(format!(
+                "`{}` takes a single argument; quote the source",
+                name
+            )),
+        },
+        "-" if rest.is_empty() => Ok(Command::Run {
+            files: vec![String::from(STDIN)],
+            watch: false,
+        }),
+        "repl" => parse_repl(rest),
+        "run" => parse_run(rest),
+        "tokens" => parse_tokens(rest),
+        "highlight" => parse_highlight(rest),
+        other => Err(format!("unknown command `{}`", other)),
+    }
+}
+
+fn parse_run(args: &[String]) -> Result<Command, String> {
+    let watch = args.iter().any(|arg| arg == "--watch");
+    let files: Vec<String> = args
+        .iter()
+        .filter(|arg| *arg != "--watch")
+        .cloned()
+        .collect();
+
+    if let Some(option) = files.iter().find(|file| file.starts_with("--")) {
+        return Err(format!("unknown option `{}` for `run`", option));
+    }
+
+    match files.as_slice() {
+        [] => return Err(String::from("`run` needs a file")),
+        [file] if watch && file == STDIN => {
+            return Err(String::from("`--watch` needs a file, not stdin"))
+        }
+        [_, _, ..] if watch => return Err(String::from("`--watch` takes a single file")),
+        _ => {}
+    }
+
+    Ok(Command::Run { files, watch })
+}
+
+fn parse_tokens(args: &[String]) -> Result<Command, String> {
+    match args {
+        [option, before, after] if option == "--diff" => Ok(Command::Diff {
+            before: before.clone(),
+            after: after.clone(),
+        }),
+        [option, ..] if option == "--diff" => Err(String::from("`--diff` needs two files")),
+        _ => Ok(Command::Tokens {
+            file: parse_file("tokens", args)?,
+        }),
+    }
+}
+
+fn parse_highlight(args: &[String]) -> Result<Command, String> {
+    let html = args.iter().any(|arg| arg == "--html");
+    let files: Vec<String> = args
+        .iter()
+        .filter(|arg| *arg != "--html")
+        .cloned()
+        .collect();
+
+    Ok(Command::Highlight {
+        file: parse_file("highlight", &files)?,
+        html,
+    })
+}
+
+fn parse_repl(args: &[String]) -> Result<Command, String> {
+    let mut no_color = false;
+    let mut listen = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-color" => no_color = true,
+            "--listen" => match args.next() {
+                Some(address) => listen = Some(address.clone()),
+                None => return Err(String::from("`--listen` needs an address")),
+            },
+            other => return Err(format!("unknown option `{}` for `repl`", other)),
+        }
+    }
+
+    Ok(Command::Repl { no_color, listen })
+}
+
+/// Reads the single file argument of a command.
+fn parse_file(command: &str, args: &[String]) -> Result<String, String> {
+    match args {
+        [file] if !file.starts_with("--") => Ok(file.clone()),
+        [] => Err(format!("`{}` needs a file", command)),
+        _ => Err(format!("`{}` takes a single file", command)),
+    }
+}
+
+#[test]
+fn it_should_parse_commands() {
+    let args = |line: &str| -> Vec<String> { line.split_whitespace().map(String::from).collect() };
+
+    assert_eq!(
+        parse(&args("run src/main.mky")),
+        Ok(Command::Run {
+            files: vec![String::from("src/main.mky")],
+            watch: false
+        })
+    );
+    assert_eq!(
+        parse(&args("run --watch src/main.mky")),
+        Ok(Command::Run {
+            files: vec![String::from("src/main.mky")],
+            watch: true
+        })
+    );
+    assert_eq!(
+        parse(&args("repl --no-color --listen 127.0.0.1:7007")),
+        Ok(Command::Repl {
+            no_color: true,
+            listen: Some(String::from("127.0.0.1:7007"))
+        })
+    );
+    assert_eq!(
+        parse(&[String::from("-e"), String::from("puts(1 + 2)")]),
+        Ok(Command::Eval {
+            source: String::from("puts(1 + 2)")
+        })
+    );
+    assert_eq!(
+        parse(&args("-")),
+        Ok(Command::Run {
+            files: vec![String::from(STDIN)],
+            watch: false
+        })
+    );
+    assert_eq!(
+        parse(&args("tokens -")),
+        Ok(Command::Tokens {
+            file: String::from(STDIN)
+        })
+    );
+    assert_eq!(
+        parse(&args("run a.mky b.mky")),
+        Ok(Command::Run {
+            files: vec![String::from("a.mky"), String::from("b.mky")],
+            watch: false
+        })
+    );
+    assert_eq!(
+        parse(&args("tokens --diff before.mky after.mky")),
+        Ok(Command::Diff {
+            before: String::from("before.mky"),
+            after: String::from("after.mky")
+        })
+    );
+    assert_eq!(
+        parse(&args("highlight --html src/main.mky")),
+        Ok(Command::Highlight {
+            file: String::from("src/main.mky"),
+            html: true
+        })
+    );
+    assert_eq!(
+        parse(&args("-e -V")),
+        Ok(Command::Eval {
+            source: String::from("-V")
+        })
+    );
+    assert_eq!(
+        parse(&args("--eval -h")),
+        Ok(Command::Eval {
+            source: String::from("-h")
+        })
+    );
+    assert_eq!(
+        parse(&args("repl --listen --version")),
+        Ok(Command::Repl {
+            no_color: false,
+            listen: Some(String::from("--version"))
+        })
+    );
+    assert_eq!(parse(&args("tokens --help")), Ok(Command::Help));
+    assert_eq!(parse(&args("--version")), Ok(Command::Version));
+}
+
+#[test]
+fn it_should_reject_bad_arguments() {
+    let args = |line: &str| -> Vec<String> { line.split_whitespace().map(String::from).collect() };
+
+    assert_eq!(parse(&[]), Err(String::from("no command given")));
+    assert_eq!(
+        parse_options(&args("-e -v")),
+        Ok((Options::default(), args("-e -v")))
+    );
+    assert_eq!(
+        parse_options(&args("repl --listen --time")),
+        Ok((Options::default(), args("repl --listen --time")))
+    );
+    assert_eq!(
+        parse(&args("-e -v --time")),
+        Err(String::from(
+            "`-e` takes a single argument; quote the source"
+        ))
+    );
+    assert_eq!(
+        parse(&args("run --watch -")),
+        Err(String::from("`--watch` needs a file, not stdin"))
+    );
+    assert_eq!(
+        parse(&args("run --watch a.mky b.mky")),
+        Err(String::from("`--watch` takes a single file"))
+    );
+    assert_eq!(
+        parse(&args("tokens")),
+        Err(String::from("`tokens` needs a file"))
+    );
+    assert_eq!(
+        parse(&args("tokens --diff a.mky")),
+        Err(String::from("`--diff` needs two files"))
+    );
+    assert_eq!(
+        parse(&args("repl --listen")),
+        Err(String::from("`--listen` needs an address"))
+    );
+    assert_eq!(
+        parse(&args("compile a.mky")),
+        Err(String::from("unknown command `compile`"))
+    );
+}
+
+#[test]
+fn it_should_parse_global_options() {
+    let args = |line: &str| -> Vec<String> { line.split_whitespace().map(String::from).collect() };
+
+    assert_eq!(
+        parse_options(&args("run --format json a.mky --time")),
+        Ok((
+            Options {
+                format: Format::Json,
+                time: true,
+                verbosity: 0,
+                emit: None
+            },
+            args("run a.mky")
+        ))
+    );
+    assert_eq!(
+        parse_options(&args("run a.mky --format yaml")),
+        Err(String::from("unknown format `yaml`"))
+    );
+    assert_eq!(
+        parse_options(&args("run --emit tokens a.mky")).map(|(options, _)| options.emit),
+        Ok(Some(Emit::Tokens))
+    );
+    assert_eq!(
+        parse_options(&args("run --emit ast a.mky")),
+        Err(String::from(
+            "`--emit ast` is not available yet; only `tokens` is"
+        ))
+    );
+    assert_eq!(
+        parse_options(&args("-vv run a.mky --verbose")).map(|(options, _)| options.verbosity),
+        Ok(3)
+    );
+}
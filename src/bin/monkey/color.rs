@@ -0,0 +1,80 @@
+use std::env;
+use std::io::{stdout, IsTerminal};
+
+use monkeylang::{Token, TokenType};
+
+/// ANSI colors used when printing Monkey code and results.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Magenta,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Red => "31",
+            Color::Green => "32",
+            Color::Yellow => "33",
+            Color::Magenta => "35",
+        }
+    }
+}
+
+/// Checks whether output should be colored.
+/// Colors are skipped when `NO_COLOR` is set or stdout isn't a terminal.
+pub fn is_enabled() -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+    !no_color && stdout().is_terminal()
+}
+
+/// Wraps `text` in the escape codes for `color`.
+pub fn paint(text: &str, color: Color) -> String {
+    format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+}
+
+/// Picks the color a token is highlighted with; `None` leaves it uncolored.
+pub fn token_color(t_type: &TokenType) -> Option<Color> {
+    match t_type {
+        TokenType::FUNCTION
+        | TokenType::LET
+        | TokenType::TRUE
+        | TokenType::FALSE
+        | TokenType::IF
+        | TokenType::ELSE
+        | TokenType::RETURN => Some(Color::Magenta),
+        TokenType::INT(_) => Some(Color::Yellow),
+        TokenType::ILLEGAL => Some(Color::Red),
+        _ => None,
+    }
+}
+
+/// Formats a token for a result; its span is left out, since offsets only add noise there.
+pub fn format_token(token: &Token) -> String {
+    format!("Token {{ t_type: {:?} }}", token.t_type)
+}
+
+/// Formats a token like [`format_token`], highlighted by its kind.
+pub fn paint_token(token: &Token) -> String {
+    let text = format_token(token);
+
+    match token_color(&token.t_type) {
+        Some(color) => paint(&text, color),
+        None => text,
+    }
+}
+
+#[test]
+fn it_should_paint_tokens() {
+    assert_eq!(
+        paint_token(&Token::new(TokenType::LET)),
+        "\x1b[35mToken { t_type: LET }\x1b[0m"
+    );
+    assert_eq!(
+        paint_token(&Token::new(TokenType::ASSIGN)),
+        "Token { t_type: ASSIGN }"
+    );
+}
@@ -0,0 +1,70 @@
+/// How an item of one sequence relates to the other, by its index in each.
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    /// The item is in both; holds its index in `before` and in `after`.
+    Same(usize, usize),
+    /// The item is only in `before`.
+    Removed(usize),
+    /// The item is only in `after`.
+    Added(usize),
+}
+
+/// Finds the smallest set of changes which turns `before` into `after`, in order.
+/// Uses the longest common subsequence, which is quadratic but fine for the size of programs
+/// in the walkthrough.
+pub fn diff<T: PartialEq>(before: &[T], after: &[T]) -> Vec<Change> {
+    // `common[i][j]` is the length of the longest common subsequence of `before[i..]` and `after[j..]`.
+    let mut common = vec![vec![0; after.len() + 1]; before.len() + 1];
+
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            common[i][j] = if before[i] == after[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = vec![];
+    let (mut i, mut j) = (0, 0);
+
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            changes.push(Change::Same(i, j));
+            i += 1;
+            j += 1;
+        } else if j == after.len() || (i < before.len() && common[i + 1][j] >= common[i][j + 1]) {
+            changes.push(Change::Removed(i));
+            i += 1;
+        } else {
+            changes.push(Change::Added(j));
+            j += 1;
+        }
+    }
+
+    changes
+}
+
+#[test]
+fn it_should_diff_sequences() {
+    // Arrange
+    let before = ["let", "a", "=", "5", ";"];
+    let after = ["let", "b", "=", "5", "+", "1", ";"];
+    let expected = vec![
+        Change::Same(0, 0),
+        Change::Removed(1),
+        Change::Added(1),
+        Change::Same(2, 2),
+        Change::Same(3, 3),
+        Change::Added(4),
+        Change::Added(5),
+        Change::Same(4, 6),
+    ];
+
+    // Act
+    let changes = diff(&before, &after);
+
+    // Assert
+    assert_eq!(changes, expected);
+}
@@ -0,0 +1,74 @@
+use monkeylang::{Lexer, TokenType};
+
+use crate::color::{self, Color};
+
+/// Highlights `source` for a terminal, coloring each token like results in the REPL.
+pub fn ansi(source: &str) -> String {
+    highlight(source, |text, t_type| match color::token_color(t_type) {
+        Some(color) => color::paint(text, color),
+        None => String::from(text),
+    })
+}
+
+/// Highlights `source` as an HTML `<pre>` block for embedding in a web page.
+/// Tokens are wrapped in `<span>`s with a class per kind, for a stylesheet to color.
+pub fn html(source: &str) -> String {
+    let code = highlight(source, |text, t_type| {
+        let text = escape_html(text);
+
+        match token_class(t_type) {
+            Some(class) => format!("<span class=\"{}\">{}</span>", class, text),
+            None => text,
+        }
+    });
+
+    format!("<pre class=\"monkey\"><code>{}</code></pre>", code)
+}
+
+/// Joins the text of every token in `source`, whitespace included, as formatted by `paint`.
+fn highlight(source: &str, paint: impl Fn(&str, &TokenType) -> String) -> String {
+    Lexer::new(source)
+        .with_trivia()
+        .read()
+        .iter()
+        .map(|token| paint(&source[token.span.start..token.span.end], &token.t_type))
+        .collect()
+}
+
+/// Names the CSS class for a token, following the colors used in the terminal.
+fn token_class(t_type: &TokenType) -> Option<&'static str> {
+    match color::token_color(t_type)? {
+        Color::Magenta => Some("keyword"),
+        Color::Yellow => Some("number"),
+        Color::Red => Some("error"),
+        Color::Green => None,
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[test]
+fn it_should_highlight_source() {
+    // Arrange
+    let source = "let a = 5 < $;";
+
+    // Act
+    let terminal = ansi(source);
+    let page = html(source);
+
+    // Assert
+    assert_eq!(
+        terminal,
+        "\x1b[35mlet\x1b[0m a = \x1b[33m5\x1b[0m < \x1b[31m$\x1b[0m;"
+    );
+    assert_eq!(
+        page,
+        "<pre class=\"monkey\"><code><span class=\"keyword\">let</span> a = \
+         <span class=\"number\">5</span> &lt; <span class=\"error\">$</span>;</code></pre>"
+    );
+}
@@ -0,0 +1,286 @@
+mod cli;
+mod color;
+mod diff;
+mod highlight;
+mod repl;
+mod trace;
+mod watch;
+use std::env;
+use std::fs;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::process::ExitCode;
+
+use monkeylang::source_map::FileId;
+use monkeylang::timing::Timings;
+use monkeylang::{Diagnostic, Lexer, SourceMap, Token, TokenType};
+
+use crate::cli::{Command, Emit, Failure, Format, Options};
+use crate::color::Color;
+use crate::diff::Change;
+use crate::trace::Trace;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match execute(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => failure.into(),
+    }
+}
+
+/// Runs the command described by `args`, reporting any errors on stderr.
+fn execute(args: &[String]) -> Result<(), Failure> {
+    let parsed = cli::parse_options(args).and_then(|(options, rest)| {
+        let command = cli::parse(&rest)?;
+
+        Ok((options, command))
+    });
+
+    let (options, command) = match parsed {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, cli::USAGE);
+            return Err(Failure::Usage);
+        }
+    };
+
+    let mut sources = SourceMap::new();
+
+    match command {
+        Command::Help => writeln!(stdout(), "{}", cli::USAGE).map_err(output_failure)?,
+        Command::Version => {
+            writeln!(stdout(), "monkey {}", env!("CARGO_PKG_VERSION")).map_err(output_failure)?
+        }
+        Command::Repl {
+            listen: Some(address),
+            ..
+        } => repl::repl::listen(&address).map_err(|error| {
+            eprintln!("error: could not serve the REPL on {}: {}", address, error);
+            Failure::Io
+        })?,
+        Command::Repl { no_color, .. } => repl::repl::run(color::is_enabled() && !no_color)?,
+        Command::Eval { source } => {
+            let file = sources.add("<eval>", source);
+
+            run(&sources, file, &options)?;
+        }
+        Command::Run { files, watch: true } => {
+            // Fail like a plain `run` when the file can't be read to begin with.
+            let first = add_file(&mut sources, &files[0])?;
+
+            watch::watch(&files[0], sources.source(first), &mut stdout(), |source| {
+                let mut sources = SourceMap::new();
+                let file = sources.add(files[0].as_str(), source);
+
+                // Diagnostics are already printed; keep watching for the next change,
+                // unless the output can no longer be written.
+                match run(&sources, file, &options) {
+                    Err(Failure::Syntax) => Ok(()),
+                    result => result,
+                }
+            })?
+        }
+        // Stops at the first file which fails, like an error part way through one program.
+        Command::Run { files, .. } => {
+            for file in files {
+                run_file(&mut sources, &file, &options)?;
+            }
+        }
+        Command::Tokens { file } => {
+            let options = Options {
+                emit: Some(Emit::Tokens),
+                ..options
+            };
+
+            run_file(&mut sources, &file, &options)?
+        }
+        Command::Highlight { file, html } => {
+            let file = add_file(&mut sources, &file)?;
+            let source = sources.source(file);
+            let mut out = stdout().lock();
+
+            if html {
+                writeln!(out, "{}", highlight::html(source))
+            } else if color::is_enabled() {
+                write!(out, "{}", highlight::ansi(source))
+            } else {
+                write!(out, "{}", source)
+            }
+            .map_err(output_failure)?;
+        }
+        Command::Diff { before, after } => {
+            let before = add_file(&mut sources, &before)?;
+            let after = add_file(&mut sources, &after)?;
+
+            diff_tokens(&sources, before, after, color::is_enabled())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reports a failure to write results to stdout.
+/// A closed pipe, like `monkey run a.mky | head`, only means the reader has seen enough,
+/// so it ends the process without a message.
+fn output_failure(error: io::Error) -> Failure {
+    if error.kind() != io::ErrorKind::BrokenPipe {
+        eprintln!("error: could not write the output: {}", error);
+    }
+
+    Failure::Io
+}
+
+/// Reads `file` into `sources` and runs it through the pipeline.
+fn run_file(sources: &mut SourceMap, file: &str, options: &Options) -> Result<(), Failure> {
+    let file = add_file(sources, file)?;
+
+    run(sources, file, options)
+}
+
+/// Reads `file`, or stdin when it's `-`, into `sources`.
+fn add_file(sources: &mut SourceMap, file: &str) -> Result<FileId, Failure> {
+    let input = read_source(file).map_err(|error| {
+        eprintln!("error: could not read `{}`: {}", file, error);
+        Failure::NoInput
+    })?;
+
+    let name = if file == cli::STDIN { "<stdin>" } else { file };
+
+    Ok(sources.add(name, input))
+}
+
+/// Reads the source of a program from `file`, or from stdin when it's `-`.
+fn read_source(file: &str) -> io::Result<String> {
+    if file != cli::STDIN {
+        return fs::read_to_string(file);
+    }
+
+    let mut source = String::new();
+    stdin().read_to_string(&mut source)?;
+
+    Ok(source)
+}
+
+/// Runs a program through the pipeline, printing its errors, or the output of the stage
+/// picked with `--emit`. Lexing is the last stage of the pipeline so far.
+fn run(sources: &SourceMap, file: FileId, options: &Options) -> Result<(), Failure> {
+    let input = sources.source(file);
+    let trace = Trace::new(options.verbosity);
+    let mut timings = Timings::default();
+    let mut lexer = Lexer::new(input);
+
+    trace.step(format!(
+        "lexing {} ({} bytes)",
+        sources.name(file),
+        input.len()
+    ));
+    let tokens = timings.measure("lex", || lexer.read());
+    trace.tokens(sources, file, &tokens);
+    trace.step(format!(
+        "lexed {} tokens with {} errors",
+        tokens.len(),
+        lexer.errors().len()
+    ));
+
+    timings.count("tokens", tokens.len());
+
+    let errors = lexer.errors();
+
+    if options.time {
+        eprintln!("{}", timings);
+    }
+
+    // Tokens are printed even when some are illegal, which is often the point of looking at them.
+    if options.emit == Some(Emit::Tokens) {
+        print_tokens(sources, file, &tokens).map_err(output_failure)?;
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    for error in errors {
+        let diagnostic = Diagnostic::from_error(file, error);
+
+        match options.format {
+            Format::Text => eprintln!("{}", diagnostic.render(sources)),
+            Format::Json => eprintln!("{}", diagnostic.to_json(sources)),
+        }
+    }
+
+    Err(Failure::Syntax)
+}
+
+/// Prints the tokens of `file` one per line, each after the line and column it starts at.
+fn print_tokens(sources: &SourceMap, file: FileId, tokens: &[Token]) -> io::Result<()> {
+    let mut out = stdout().lock();
+
+    for token in tokens {
+        let location = sources.location(file, token.span.start);
+
+        writeln!(
+            out,
+            "{}:{} {:?}",
+            location.line, location.column, token.t_type
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Prints the tokens of `before` and `after` as a diff: `-` for tokens only in `before`,
+/// `+` for tokens only in `after`, each with where it is in its file.
+/// Tokens are compared by type alone, so moving code around doesn't make every later token differ.
+/// Illegal characters are compared like any other token rather than reported as errors.
+fn diff_tokens(
+    sources: &SourceMap,
+    before: FileId,
+    after: FileId,
+    color: bool,
+) -> Result<(), Failure> {
+    let tokens = |file| Lexer::new(sources.source(file)).read();
+    let (old, new) = (tokens(before), tokens(after));
+
+    let old_types: Vec<&TokenType> = old.iter().map(|token| &token.t_type).collect();
+    let new_types: Vec<&TokenType> = new.iter().map(|token| &token.t_type).collect();
+    let changes = diff::diff(&old_types, &new_types);
+
+    let mut out = stdout().lock();
+
+    writeln!(
+        out,
+        "--- {}\n+++ {}",
+        sources.name(before),
+        sources.name(after)
+    )
+    .map_err(output_failure)?;
+
+    for change in &changes {
+        let (sign, file, token, line_color) = match *change {
+            Change::Same(_, index) => (' ', after, &new[index], None),
+            Change::Removed(index) => ('-', before, &old[index], Some(Color::Red)),
+            Change::Added(index) => ('+', after, &new[index], Some(Color::Green)),
+        };
+
+        let location = sources.location(file, token.span.start);
+        let line = format!(
+            "{} {}:{} {:?}",
+            sign, location.line, location.column, token.t_type
+        );
+
+        match line_color {
+            Some(line_color) if color => writeln!(out, "{}", color::paint(&line, line_color)),
+            _ => writeln!(out, "{}", line),
+        }
+        .map_err(output_failure)?;
+    }
+
+    if changes
+        .iter()
+        .all(|change| matches!(change, Change::Same(..)))
+    {
+        Ok(())
+    } else {
+        Err(Failure::Different)
+    }
+}
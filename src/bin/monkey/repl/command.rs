@@ -0,0 +1,70 @@
+/// A REPL meta-command, entered as `:name` on its own line.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Cancel,
+    Full,
+    Help,
+    Load(String),
+    Quit,
+    Replay(String),
+    Save(String),
+    Time,
+    Unknown(String),
+}
+
+impl Command {
+    /// Parses a line of input as a meta-command.
+    /// Returns `None` when the line is Monkey source rather than a `:command`.
+    pub fn parse(input: &str) -> Option<Self> {
+        let line = input.trim().strip_prefix(':')?;
+        let (name, argument) = match line.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (line, ""),
+        };
+
+        let command = match name {
+            "cancel" | "c" => Command::Cancel,
+            "full" | "f" => Command::Full,
+            "help" | "h" => Command::Help,
+            "load" | "l" => Command::Load(String::from(argument)),
+            "quit" | "q" => Command::Quit,
+            "replay" => Command::Replay(String::from(argument)),
+            "save" => Command::Save(String::from(argument)),
+            "time" | "t" => Command::Time,
+            other => Command::Unknown(String::from(other)),
+        };
+
+        Some(command)
+    }
+}
+
+/// Lists the available meta-commands.
+pub const HELP: &str = "\
+:cancel        Drop an unfinished input and return to the main prompt.
+:full          Show the whole result of the last input.
+:help          Show this message.
+:load <file>   Run a Monkey file in the REPL.
+:quit          Leave the REPL.
+:replay <file> Enter each line of a file as if it were typed.
+:save <file>   Write every input which evaluated without errors to a file.
+:time          Show how long each phase of the last input took.";
+
+#[test]
+fn it_should_parse_commands() {
+    assert_eq!(Command::parse(":help\n"), Some(Command::Help));
+    assert_eq!(Command::parse("  :q  "), Some(Command::Quit));
+    assert_eq!(
+        Command::parse(":load  src/main.mky\n"),
+        Some(Command::Load(String::from("src/main.mky")))
+    );
+    assert_eq!(
+        Command::parse(":nope"),
+        Some(Command::Unknown(String::from("nope")))
+    );
+    assert_eq!(
+        Command::parse(":save session.mky"),
+        Some(Command::Save(String::from("session.mky")))
+    );
+    assert_eq!(Command::parse(":cancel"), Some(Command::Cancel));
+    assert_eq!(Command::parse("let a = 5;"), None);
+}
@@ -0,0 +1,5 @@
+mod command;
+mod output;
+mod prompt;
+#[allow(clippy::module_inception)]
+pub mod repl;
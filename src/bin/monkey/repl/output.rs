@@ -0,0 +1,65 @@
+use monkeylang::Token;
+
+use crate::color;
+
+/// Results with more items than this are printed one item per line and truncated.
+pub const MAX_ITEMS: usize = 32;
+
+/// Formats a token stream for printing.
+/// Long streams are split over indented lines; when a `limit` is given, items past it
+/// are summarized as `... (N more items)`.
+pub fn format_tokens(tokens: &[Token], limit: Option<usize>, color: bool) -> String {
+    let items: Vec<String> = tokens
+        .iter()
+        .map(|token| {
+            if color {
+                color::paint_token(token)
+            } else {
+                color::format_token(token)
+            }
+        })
+        .collect();
+
+    if items.len() <= MAX_ITEMS {
+        return format!("[{}]", items.join(", "));
+    }
+
+    let shown = limit.unwrap_or(items.len()).min(items.len());
+    let mut lines: Vec<String> = items[..shown]
+        .iter()
+        .map(|item| format!("    {},", item))
+        .collect();
+
+    if shown < items.len() {
+        lines.push(format!("    ... ({} more items)", items.len() - shown));
+    }
+
+    format!("[\n{}\n]", lines.join("\n"))
+}
+
+#[test]
+fn it_should_truncate_long_results() {
+    // Arrange
+    let tokens: Vec<Token> = (0..40)
+        .map(|value| Token::new(monkeylang::TokenType::INT(value)))
+        .collect();
+
+    // Act
+    let truncated = format_tokens(&tokens, Some(MAX_ITEMS), false);
+    let full = format_tokens(&tokens, None, false);
+
+    // Assert
+    assert_eq!(truncated.lines().count(), MAX_ITEMS + 3);
+    assert!(truncated.contains("    ... (8 more items)"));
+    assert_eq!(full.lines().count(), 40 + 2);
+}
+
+#[test]
+fn it_should_keep_short_results_on_one_line() {
+    let tokens = vec![Token::new(monkeylang::TokenType::PLUS)];
+
+    assert_eq!(
+        format_tokens(&tokens, Some(MAX_ITEMS), false),
+        "[Token { t_type: PLUS }]"
+    );
+}
@@ -0,0 +1,65 @@
+use std::env;
+
+/// Environment variable overriding the primary prompt.
+const PROMPT_VAR: &str = "MONKEY_PROMPT";
+/// Environment variable which, when set, shows the nesting depth in the continuation prompt.
+const PROMPT_DEPTH_VAR: &str = "MONKEY_PROMPT_DEPTH";
+
+/// The prompts printed before each line of REPL input.
+#[derive(Debug, PartialEq)]
+pub struct Prompt {
+    /// Printed when the REPL waits for a new input.
+    primary: String,
+    /// Printed while a multi-line input still has open blocks.
+    continuation: String,
+    /// Whether the continuation prompt includes the number of open blocks.
+    show_depth: bool,
+}
+
+impl Prompt {
+    /// Reads the prompt configuration from `MONKEY_PROMPT` and `MONKEY_PROMPT_DEPTH`.
+    pub fn from_env() -> Self {
+        let mut prompt = Prompt::default();
+
+        if let Ok(primary) = env::var(PROMPT_VAR) {
+            prompt.primary = primary;
+        }
+
+        prompt.show_depth = env::var_os(PROMPT_DEPTH_VAR).is_some();
+
+        prompt
+    }
+
+    /// Gets the prompt to print for the given nesting `depth` of the pending input.
+    pub fn render(&self, depth: i32) -> String {
+        if depth <= 0 {
+            self.primary.clone()
+        } else if self.show_depth {
+            format!("[{}]{}", depth, self.continuation)
+        } else {
+            self.continuation.clone()
+        }
+    }
+}
+
+impl Default for Prompt {
+    fn default() -> Self {
+        Prompt {
+            primary: String::from(">> "),
+            continuation: String::from(".. "),
+            show_depth: false,
+        }
+    }
+}
+
+#[test]
+fn it_should_render_prompts() {
+    let mut prompt = Prompt::default();
+
+    assert_eq!(prompt.render(0), ">> ");
+    assert_eq!(prompt.render(2), ".. ");
+
+    prompt.show_depth = true;
+
+    assert_eq!(prompt.render(2), "[2].. ");
+}
@@ -0,0 +1,421 @@
+use std::fs;
+use std::io::{self, stderr, stdin, stdout, BufRead, BufReader, IsTerminal, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use monkeylang::source_map::FileId;
+use monkeylang::timing::Timings;
+use monkeylang::{Diagnostic, Lexer, SourceMap, TokenType};
+
+use crate::cli::Failure;
+use crate::repl::command::{Command, HELP};
+use crate::repl::output::{self, MAX_ITEMS};
+use crate::repl::prompt::Prompt;
+
+/// Starts the REPL on stdin/stdout; `color` highlights the printed results.
+pub fn run(color: bool) -> Result<(), Failure> {
+    if !stdin().is_terminal() {
+        return run_piped(color);
+    }
+
+    let mut repl = Repl::new(color, Box::new(stdout()), Box::new(stderr()));
+
+    repl.print_banner().map_err(io_failure)?;
+
+    loop {
+        repl.print_prompt().map_err(io_failure)?;
+
+        let mut input = String::new();
+
+        let read = stdin().read_line(&mut input).map_err(io_failure)?;
+
+        // Ctrl-D closes stdin; leave the REPL instead of spinning on empty reads.
+        if read == 0 {
+            println!();
+            return Ok(());
+        }
+
+        if !repl.handle_line(&input).map_err(io_failure)? {
+            return Ok(());
+        }
+    }
+}
+
+/// Reports a failure to talk to the terminal.
+fn io_failure(error: io::Error) -> Failure {
+    eprintln!("error: {}", error);
+
+    Failure::Io
+}
+
+/// Serves the REPL over TCP on `address`, giving every connection its own session.
+pub fn listen(address: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+
+    println!("Monkey REPL listening on {}.", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        // A failed accept, like running out of file descriptors, only loses that connection.
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("error: could not accept a connection: {}", error);
+                continue;
+            }
+        };
+
+        thread::spawn(move || {
+            // A client hanging up mid-session only ends that session.
+            let _ = serve(stream);
+        });
+    }
+
+    Ok(())
+}
+
+/// Runs one remote REPL session until the client quits or disconnects.
+fn serve(stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut repl = Repl::new(false, Box::new(stream.try_clone()?), Box::new(stream));
+
+    // Remote clients shouldn't be able to read or write files on the host.
+    repl.allow_files = false;
+    repl.print_banner()?;
+
+    loop {
+        repl.print_prompt()?;
+
+        let mut input = String::new();
+
+        if reader.read_line(&mut input)? == 0 || !repl.handle_line(&input)? {
+            return Ok(());
+        }
+    }
+}
+
+/// The state of an interactive session.
+struct Repl {
+    color: bool,
+    /// Whether `:load`, `:save` and `:replay` may touch the filesystem.
+    allow_files: bool,
+    /// Where results are printed.
+    out: Box<dyn Write + Send>,
+    /// Where diagnostics are printed.
+    err: Box<dyn Write + Send>,
+    prompt: Prompt,
+    /// Lines of an input whose blocks aren't closed yet.
+    buffer: String,
+    /// Every input and loaded file of the session, so errors point at the right one.
+    sources: SourceMap,
+    /// How many inputs have been entered, used to name them in `sources`.
+    inputs: usize,
+    last_timings: Option<Timings>,
+    /// The last input which evaluated without errors, kept for `:full`.
+    last_input: Option<FileId>,
+    /// Every input of the session which evaluated without errors.
+    transcript: Vec<String>,
+    /// The files being replayed, innermost last, so a file can't replay itself forever.
+    replaying: Vec<PathBuf>,
+}
+
+impl Repl {
+    fn new(color: bool, out: Box<dyn Write + Send>, err: Box<dyn Write + Send>) -> Self {
+        Repl {
+            color,
+            allow_files: true,
+            out,
+            err,
+            prompt: Prompt::from_env(),
+            buffer: String::new(),
+            sources: SourceMap::new(),
+            inputs: 0,
+            last_timings: None,
+            last_input: None,
+            transcript: vec![],
+            replaying: vec![],
+        }
+    }
+
+    fn print_banner(&mut self) -> io::Result<()> {
+        writeln!(self.out, "Entering Monkey REPL.")?;
+        writeln!(self.out, "Enter ':help' for commands or ':quit' to quit.")
+    }
+
+    fn print_prompt(&mut self) -> io::Result<()> {
+        write!(
+            self.out,
+            "{}",
+            self.prompt.render(nesting_depth(&self.buffer))
+        )?;
+        self.out.flush()
+    }
+
+    /// Handles one line of input; returns `false` once the REPL should quit.
+    fn handle_line(&mut self, line: &str) -> io::Result<bool> {
+        // Monkey source never starts with `:`, so commands work even part way through an input.
+        if let Some(command) = Command::parse(line) {
+            return self.run_command(command);
+        }
+
+        self.buffer.push_str(line);
+
+        // Keep reading lines until every opened block and parenthesis is closed.
+        if nesting_depth(&self.buffer) > 0 {
+            return Ok(true);
+        }
+
+        let input = std::mem::take(&mut self.buffer);
+
+        if input.trim().is_empty() {
+            return Ok(true);
+        }
+
+        self.inputs += 1;
+
+        let file = self.sources.add(format!("<repl:{}>", self.inputs), input);
+        let evaluation = self.evaluate(file, Some(MAX_ITEMS))?;
+
+        if evaluation.succeeded {
+            let input = self.sources.source(file).trim_end();
+
+            self.transcript.push(format!("{}\n", input));
+            self.last_input = Some(file);
+        }
+
+        self.last_timings = Some(evaluation.timings);
+
+        Ok(true)
+    }
+
+    /// Runs a meta-command; returns `false` once the REPL should quit.
+    fn run_command(&mut self, command: Command) -> io::Result<bool> {
+        match command {
+            Command::Load(_) | Command::Replay(_) | Command::Save(_) if !self.allow_files => {
+                writeln!(self.out, "Files can't be used in this session.")?;
+            }
+            Command::Cancel if self.buffer.is_empty() => {
+                writeln!(self.out, "There is no unfinished input.")?
+            }
+            Command::Cancel => self.buffer.clear(),
+            Command::Full => match self.last_input {
+                Some(file) => {
+                    self.evaluate(file, None)?;
+                }
+                None => writeln!(self.out, "Nothing has been evaluated yet.")?,
+            },
+            Command::Help => writeln!(self.out, "{}", HELP)?,
+            Command::Load(path) => match fs::read_to_string(&path) {
+                Ok(source) => {
+                    let file = self.sources.add(path, source);
+                    let evaluation = self.evaluate(file, Some(MAX_ITEMS))?;
+
+                    if evaluation.succeeded {
+                        self.last_input = Some(file);
+                    }
+
+                    self.last_timings = Some(evaluation.timings);
+                }
+                Err(error) => writeln!(self.out, "Could not load '{}': {}", path, error)?,
+            },
+            Command::Quit => return Ok(false),
+            Command::Replay(path) => return self.replay(&path),
+            Command::Save(path) => match fs::write(&path, self.transcript.concat()) {
+                Ok(()) => writeln!(
+                    self.out,
+                    "Saved {} inputs to '{}'.",
+                    self.transcript.len(),
+                    path
+                )?,
+                Err(error) => writeln!(self.out, "Could not save '{}': {}", path, error)?,
+            },
+            Command::Time => match &self.last_timings {
+                Some(timings) => writeln!(self.out, "{}", timings)?,
+                None => writeln!(self.out, "Nothing has been evaluated yet.")?,
+            },
+            Command::Unknown(name) => writeln!(
+                self.out,
+                "Unknown command ':{}'. Enter ':help' for commands.",
+                name
+            )?,
+        }
+
+        Ok(true)
+    }
+
+    /// Enters each line of the file at `path` as if it were typed; returns `false` once the
+    /// REPL should quit.
+    fn replay(&mut self, path: &str) -> io::Result<bool> {
+        // The same file can be reached through different paths, so compare where they lead.
+        let key = Path::new(path)
+            .canonicalize()
+            .unwrap_or_else(|_| PathBuf::from(path));
+
+        if self.replaying.contains(&key) {
+            writeln!(self.out, "Already replaying '{}'.", path)?;
+            return Ok(true);
+        }
+
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(error) => {
+                writeln!(self.out, "Could not replay '{}': {}", path, error)?;
+                return Ok(true);
+            }
+        };
+
+        self.replaying.push(key);
+
+        let result = self.enter_lines(&source);
+
+        self.replaying.pop();
+
+        result
+    }
+
+    /// Enters each line of `source`, echoing it after the prompt; returns `false` once the
+    /// REPL should quit.
+    fn enter_lines(&mut self, source: &str) -> io::Result<bool> {
+        for line in source.lines() {
+            self.print_prompt()?;
+            writeln!(self.out, "{}", line)?;
+
+            if !self.handle_line(&format!("{}\n", line))? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Runs a source of the session through the pipeline and prints the result,
+    /// showing at most `limit` items of a large result.
+    fn evaluate(&mut self, file: FileId, limit: Option<usize>) -> io::Result<Evaluation> {
+        let input = self.sources.source(file);
+        let mut timings = Timings::default();
+        let result = timings.measure("lex", || Lexer::new(input).tokenize());
+        let succeeded = result.is_ok();
+
+        match result {
+            Ok(tokens) => {
+                timings.count("tokens", tokens.len());
+
+                let result = output::format_tokens(&tokens, limit, self.color);
+
+                writeln!(self.out, "{}", result)?;
+            }
+            Err(errors) => {
+                for error in &errors {
+                    let diagnostic = Diagnostic::from_error(file, error);
+
+                    writeln!(self.err, "{}", diagnostic.render(&self.sources))?;
+                }
+            }
+        }
+
+        Ok(Evaluation { timings, succeeded })
+    }
+}
+
+/// Evaluates everything piped into stdin as one program, without prompts or banners.
+/// Fails when the program has errors.
+fn run_piped(color: bool) -> Result<(), Failure> {
+    let mut input = String::new();
+
+    stdin().read_to_string(&mut input).map_err(io_failure)?;
+
+    let mut repl = Repl::new(color, Box::new(stdout()), Box::new(stderr()));
+    let file = repl.sources.add("<stdin>", input);
+    let evaluation = repl.evaluate(file, None).map_err(io_failure)?;
+
+    if !evaluation.succeeded {
+        return Err(Failure::Syntax);
+    }
+
+    Ok(())
+}
+
+/// The outcome of running one input through the pipeline.
+struct Evaluation {
+    timings: Timings,
+    succeeded: bool,
+}
+
+/// Counts the `(` and `{` in `input` which are still waiting for their closing pair.
+fn nesting_depth(input: &str) -> i32 {
+    Lexer::new(input)
+        .read()
+        .iter()
+        .fold(0, |depth, token| match token.t_type {
+            TokenType::LPAREN | TokenType::LBRACE => depth + 1,
+            TokenType::RPAREN | TokenType::RBRACE => depth - 1,
+            _ => depth,
+        })
+}
+
+#[test]
+fn it_should_count_unclosed_blocks() {
+    assert_eq!(nesting_depth("let add = fn(x, y) {\n"), 1);
+    assert_eq!(nesting_depth("let add = fn(x, y) {\nx + y;\n};\n"), 0);
+    assert_eq!(nesting_depth("add(1, fn() {"), 2);
+}
+
+#[test]
+fn it_should_run_commands_while_an_input_is_unfinished() {
+    // Arrange
+    let mut repl = Repl::new(false, Box::new(io::sink()), Box::new(io::sink()));
+
+    // Act
+    repl.handle_line("let add = fn(x, y) {\n").unwrap();
+    let pending = repl.buffer.clone();
+    repl.handle_line(":cancel\n").unwrap();
+    repl.handle_line("add(1, (\n").unwrap();
+    let keep_going = repl.handle_line(":quit\n").unwrap();
+
+    // Assert
+    assert_eq!(pending, "let add = fn(x, y) {\n");
+    assert_eq!(repl.inputs, 0);
+    assert_eq!(repl.buffer, "add(1, (\n");
+    assert!(!keep_going);
+}
+
+#[test]
+fn it_should_not_replay_a_file_inside_itself() {
+    // Arrange
+    let path = std::env::temp_dir().join(format!("monkey-replay-{}.mky", std::process::id()));
+    let source = format!("1;\n:replay {}\n2;\n", path.display());
+    fs::write(&path, source).unwrap();
+    let mut repl = Repl::new(false, Box::new(io::sink()), Box::new(io::sink()));
+
+    // Act
+    let keep_going = repl.replay(path.to_str().unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    // Assert
+    assert!(keep_going);
+    assert_eq!(repl.inputs, 2);
+    assert!(repl.replaying.is_empty());
+}
+
+#[test]
+fn it_should_serve_a_session_over_tcp() {
+    // Arrange
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = thread::spawn(move || serve(listener.accept().unwrap().0));
+
+    // Act
+    let mut client = TcpStream::connect(address).unwrap();
+    client
+        .write_all(b"5;\n:load src/main.mky\n:quit\n")
+        .unwrap();
+
+    let mut transcript = String::new();
+    client.read_to_string(&mut transcript).unwrap();
+    server.join().unwrap().unwrap();
+
+    // Assert
+    assert!(transcript.starts_with("Entering Monkey REPL."));
+    assert!(transcript.contains("INT(5)"));
+    assert!(transcript.contains("Files can't be used in this session."));
+}
@@ -0,0 +1,39 @@
+use std::fmt::Display;
+
+use monkeylang::source_map::FileId;
+use monkeylang::{SourceMap, Token};
+
+/// Reports what the pipeline is doing on stderr, for `-v` and `-vv`.
+#[derive(Debug, Clone, Copy)]
+pub struct Trace {
+    verbosity: u8,
+}
+
+impl Trace {
+    pub fn new(verbosity: u8) -> Self {
+        Trace { verbosity }
+    }
+
+    /// Reports a step of the pipeline, like starting to lex a file. Shown from `-v`.
+    pub fn step(&self, message: impl Display) {
+        if self.verbosity >= 1 {
+            eprintln!("trace: {}", message);
+        }
+    }
+
+    /// Reports every token lexed from `file`, with where it starts. Shown from `-vv`.
+    pub fn tokens(&self, sources: &SourceMap, file: FileId, tokens: &[Token]) {
+        if self.verbosity < 2 {
+            return;
+        }
+
+        for token in tokens {
+            let location = sources.location(file, token.span.start);
+
+            eprintln!(
+                "trace:   {}:{}:{} {:?}",
+                location.file, location.line, location.column, token.t_type
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs;
+use std::io::{self, stdout, IsTerminal, Write};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::cli::Failure;
+
+/// How often the watched file is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Runs `source`, which was just read from `file`, through `run`, then runs the file again
+/// every time it changes, until the process is stopped.
+/// On a terminal, the screen is cleared before each run so only the latest output is shown.
+/// Returns only when `run` or writing to `out` fails, like when the output is a closed pipe;
+/// `run` should only fail for errors which make watching pointless.
+pub fn watch(
+    file: &str,
+    source: &str,
+    out: &mut impl Write,
+    mut run: impl FnMut(&str) -> Result<(), Failure>,
+) -> Result<(), Failure> {
+    let mut last_modified = modified(file);
+
+    show(file, source, out, &mut run)?;
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        // Editors often replace a file when saving it, so it may be missing for a moment.
+        let modified = modified(file);
+
+        if modified.is_none() || modified == last_modified {
+            continue;
+        }
+
+        last_modified = modified;
+
+        match fs::read_to_string(file) {
+            Ok(source) => show(file, &source, out, &mut run)?,
+            Err(error) => eprintln!("error: could not read `{}`: {}", file, error),
+        }
+    }
+}
+
+/// Gets when `file` was last changed, if it can be read.
+fn modified(file: &str) -> Option<SystemTime> {
+    fs::metadata(file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Shows a fresh run of `source` under a header naming the watched `file`.
+fn show(
+    file: &str,
+    source: &str,
+    out: &mut impl Write,
+    run: &mut impl FnMut(&str) -> Result<(), Failure>,
+) -> Result<(), Failure> {
+    write_header(file, out).map_err(crate::output_failure)?;
+    run(source)
+}
+
+/// Clears a terminal and names the watched `file`.
+fn write_header(file: &str, out: &mut impl Write) -> io::Result<()> {
+    if stdout().is_terminal() {
+        write!(out, "\x1b[2J\x1b[H")?;
+    }
+
+    writeln!(out, "Watching {}. Press Ctrl-C to stop.\n", file)?;
+    out.flush()
+}
+
+#[test]
+fn it_should_stop_watching_once_output_fails() {
+    // Arrange
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut runs = 0;
+
+    // Act
+    let header_failed = watch("a.mky", "", &mut ClosedPipe, |_| Ok(()));
+    let run_failed = watch("a.mky", "", &mut io::sink(), |_| {
+        runs += 1;
+        Err(Failure::Io)
+    });
+
+    // Assert
+    assert_eq!(header_failed, Err(Failure::Io));
+    assert_eq!(run_failed, Err(Failure::Io));
+    assert_eq!(runs, 1);
+}
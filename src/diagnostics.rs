@@ -0,0 +1,200 @@
+use crate::error::MonkeyError;
+use crate::source_map::{FileId, SourceMap};
+use crate::token::Span;
+
+/// An error found in Monkey source, pointing at where it happened.
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    /// The source in which the error happened.
+    pub file: FileId,
+    /// Identifies the kind of error, e.g. `E0001`.
+    pub code: &'static str,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn new(file: FileId, code: &'static str, message: String, span: Span) -> Self {
+        Diagnostic {
+            file,
+            code,
+            message,
+            span,
+        }
+    }
+
+    /// Creates a diagnostic for an error found in `file`.
+    pub fn from_error(file: FileId, error: &MonkeyError) -> Self {
+        Diagnostic::new(file, error.code(), error.to_string(), error.span())
+    }
+
+    /// Formats the diagnostic with the offending source line and a `^^^` underline.
+    pub fn render(&self, sources: &SourceMap) -> String {
+        let source = sources.source(self.file);
+        let start = self.span.start.min(source.len());
+        let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |index| start + index);
+
+        let location = sources.location(self.file, start);
+        let width = source[start..self.span.end.clamp(start, line_end)]
+            .chars()
+            .count()
+            .max(1);
+
+        let gutter = " ".repeat(location.line.to_string().len());
+        // Keep tabs so the caret lines up however wide the terminal draws them.
+        let padding: String = source[line_start..start]
+            .chars()
+            .map(|character| if character == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        format!(
+            "error[{}]: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+            self.code,
+            self.message,
+            gutter,
+            location.file,
+            location.line,
+            location.column,
+            gutter,
+            location.line,
+            &source[line_start..line_end],
+            gutter,
+            padding,
+            "^".repeat(width),
+        )
+    }
+
+    /// Formats the diagnostic as a single line JSON object, for editors and CI to consume.
+    pub fn to_json(&self, sources: &SourceMap) -> String {
+        let location = sources.location(self.file, self.span.start);
+
+        format!(
+            "{{\"severity\":\"error\",\"code\":\"{}\",\"message\":{},\"file\":{},\"span\":{{\"start\":{},\"end\":{},\"line\":{},\"column\":{}}}}}",
+            self.code,
+            json_string(&self.message),
+            json_string(location.file),
+            self.span.start,
+            self.span.end,
+            location.line,
+            location.column,
+        )
+    }
+}
+
+/// Quotes `text` as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            control if control.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", control as u32))
+            }
+            other => quoted.push(other),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[test]
+fn it_should_render_a_caret_under_the_error() {
+    // Arrange
+    let mut sources = SourceMap::new();
+    let file = sources.add("main.mky", "let five = 5;\nlet ten = 10 $ 2;");
+    let diagnostic = Diagnostic::new(
+        file,
+        "E0001",
+        String::from("illegal character `$`"),
+        Span::new(27, 28),
+    );
+
+    // Act
+    let rendered = diagnostic.render(&sources);
+
+    // Assert
+    assert_eq!(
+        rendered,
+        "error[E0001]: illegal character `$`\n --> main.mky:2:14\n  |\n2 | let ten = 10 $ 2;\n  |              ^"
+    );
+}
+
+#[test]
+fn it_should_line_up_the_caret_after_tabs() {
+    // Arrange
+    let mut sources = SourceMap::new();
+    let file = sources.add("main.mky", "\tlet a = $;");
+    let diagnostic = Diagnostic::new(
+        file,
+        "E0001",
+        String::from("illegal character `$`"),
+        Span::new(9, 10),
+    );
+
+    // Act
+    let rendered = diagnostic.render(&sources);
+
+    // Assert
+    assert_eq!(
+        rendered,
+        "error[E0001]: illegal character `$`\n --> main.mky:1:10\n  |\n1 | \tlet a = $;\n  | \t        ^"
+    );
+}
+
+#[test]
+fn it_should_describe_lexer_errors() {
+    // Arrange
+    let mut sources = SourceMap::new();
+    let file = sources.add("<eval>", "let a = 5 $ 5;");
+    let mut lexer = crate::lexer::Lexer::new(sources.source(file));
+    lexer.read();
+
+    // Act
+    let diagnostics: Vec<Diagnostic> = lexer
+        .errors()
+        .iter()
+        .map(|error| Diagnostic::from_error(file, error))
+        .collect();
+
+    // Assert
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::new(
+            file,
+            "E0001",
+            String::from("illegal character `$`"),
+            Span::new(10, 11)
+        )]
+    );
+}
+
+#[test]
+fn it_should_format_diagnostics_as_json() {
+    // Arrange
+    let mut sources = SourceMap::new();
+    let file = sources.add("src/main.mky", "let a = \"5\";");
+    let diagnostic = Diagnostic::new(
+        file,
+        "E0001",
+        String::from("illegal character `\"`"),
+        Span::new(8, 9),
+    );
+
+    // Act
+    let json = diagnostic.to_json(&sources);
+
+    // Assert
+    assert_eq!(
+        json,
+        r#"{"severity":"error","code":"E0001","message":"illegal character `\"`","file":"src/main.mky","span":{"start":8,"end":9,"line":1,"column":9}}"#
+    );
+}
@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::token::Span;
+
+/// Everything which can go wrong in the Monkey pipeline.
+/// Each stage adds a variant as it lands; only lexing exists so far.
+#[derive(Debug, PartialEq)]
+pub enum MonkeyError {
+    /// The source contains something which isn't a valid token.
+    Lex(LexError),
+}
+
+impl MonkeyError {
+    /// A stable identifier for the kind of error, e.g. `E0001`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            MonkeyError::Lex(error) => error.code(),
+        }
+    }
+
+    /// Where in the source the error happened.
+    pub fn span(&self) -> Span {
+        match self {
+            MonkeyError::Lex(error) => error.span,
+        }
+    }
+}
+
+impl fmt::Display for MonkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MonkeyError::Lex(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for MonkeyError {}
+
+impl From<LexError> for MonkeyError {
+    fn from(error: LexError) -> Self {
+        MonkeyError::Lex(error)
+    }
+}
+
+/// An error found while reading tokens.
+#[derive(Debug, PartialEq)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LexErrorKind {
+    /// A character which doesn't start any token.
+    IllegalCharacter(char),
+    /// An integer literal which doesn't fit in an `INT` token.
+    IntegerTooLarge(String),
+}
+
+impl LexError {
+    pub fn new(kind: LexErrorKind, span: Span) -> Self {
+        LexError { kind, span }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self.kind {
+            LexErrorKind::IllegalCharacter(_) => "E0001",
+            LexErrorKind::IntegerTooLarge(_) => "E0002",
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            LexErrorKind::IllegalCharacter(character) => {
+                write!(f, "illegal character `{}`", character)
+            }
+            LexErrorKind::IntegerTooLarge(literal) => {
+                write!(f, "integer `{}` is too large", literal)
+            }
+        }
+    }
+}
+
+impl Error for LexError {}
+
+#[test]
+fn it_should_describe_errors() {
+    // Arrange
+    let error = MonkeyError::from(LexError::new(
+        LexErrorKind::IntegerTooLarge(String::from("99999999999")),
+        Span::new(4, 15),
+    ));
+
+    // Assert
+    assert_eq!(error.to_string(), "integer `99999999999` is too large");
+    assert_eq!(error.code(), "E0002");
+    assert_eq!(error.span(), Span::new(4, 15));
+}
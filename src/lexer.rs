@@ -1,5 +1,9 @@
-use crate::token::{Token, TokenType};
+use std::ops::Range;
 
+use crate::error::{LexError, LexErrorKind, MonkeyError};
+use crate::token::{Span, Token, TokenType};
+
+/// Turns Monkey source into [`Token`]s.
 #[derive(Debug)]
 pub struct Lexer<'a> {
     input: &'a str,
@@ -8,6 +12,10 @@ pub struct Lexer<'a> {
     /// Current reading position after current `ch`.
     read_position: usize,
     current_character: Option<char>,
+    /// Errors found so far; each one also produced an `ILLEGAL` token.
+    errors: Vec<MonkeyError>,
+    /// Whether whitespace is returned as tokens instead of skipped.
+    trivia: bool,
 }
 
 impl<'a> Lexer<'a> {
@@ -19,12 +27,32 @@ impl<'a> Lexer<'a> {
             position: 0,
             read_position: 0,
             current_character: None,
+            errors: vec![],
+            trivia: false,
         };
         lexer.read_char();
 
         lexer
     }
 
+    /// Returns whitespace as `WHITESPACE` tokens instead of skipping it, so the spans of
+    /// the tokens cover the whole input. Useful for tools that reproduce the source, like
+    /// formatters and highlighters.
+    pub fn with_trivia(mut self) -> Self {
+        self.trivia = true;
+        self
+    }
+
+    /// Creates a lexer which starts reading `input` at the byte offset `position`.
+    fn starting_at(input: &'a str, position: usize) -> Self {
+        let mut lexer = Lexer::new(input);
+
+        lexer.read_position = position;
+        lexer.read_char();
+
+        lexer
+    }
+
     /// Reads the given input and converts each value into a token.
     pub fn read(&mut self) -> Vec<Token> {
         let mut tokens: Vec<Token> = vec![];
@@ -33,7 +61,9 @@ impl<'a> Lexer<'a> {
             let token = self.next_token();
 
             if token.is_none() {
-                tokens.push(Token::new(TokenType::EOF));
+                let end = Span::new(self.position, self.position);
+
+                tokens.push(Token::with_span(TokenType::EOF, end));
                 break;
             }
 
@@ -43,67 +73,132 @@ impl<'a> Lexer<'a> {
         tokens
     }
 
-    /// Reads a char from the input and advances the `read_position`.
-    /// `ch` gets set to a null value when we read the end of the input.
-    fn read_char(&mut self) {
-        if self.is_end_of_file() {
-            self.current_character = None;
-        } else {
-            self.current_character = self.input.chars().nth(self.read_position);
+    /// Lexes `input` after an edit replaced the byte range `edited` of the previous source with
+    /// `new_text`, reusing the `previous` tokens which the edit couldn't have changed.
+    ///
+    /// Tokens before the edit are kept as they are. Lexing starts again from the last of them and
+    /// stops as soon as it reaches a token which also started the same text before the edit;
+    /// from there on the previous tokens are reused, shifted by the change in length.
+    /// The result is the same as lexing the whole of `input` again, without trivia.
+    pub fn relex(
+        input: &'a str,
+        edited: Range<usize>,
+        new_text: &str,
+        previous: &[Token],
+    ) -> Vec<Token> {
+        let edited_end = edited.start + new_text.len();
+        let shift = |offset: usize| offset + edited_end - edited.end;
+
+        // A token ending right at the edit could run into the new text, so it's lexed again too.
+        let kept = previous
+            .iter()
+            .take_while(|token| token.span.end < edited.start && token.t_type != TokenType::EOF)
+            .count();
+        let restart = kept
+            .checked_sub(1)
+            .map_or(0, |last| previous[last].span.end);
+
+        let mut tokens = previous[..kept].to_vec();
+        let mut lexer = Lexer::starting_at(input, restart);
+
+        while let Some(token) = lexer.next_token() {
+            if token.span.start >= edited_end {
+                let old_start = token.span.start + edited.end - edited_end;
+
+                if let Ok(index) = previous.binary_search_by_key(&old_start, |old| old.span.start) {
+                    tokens.extend(previous[index..].iter().map(|old| {
+                        let span = Span::new(shift(old.span.start), shift(old.span.end));
+
+                        Token::with_span(old.t_type.clone(), span)
+                    }));
+
+                    return tokens;
+                }
+            }
+
+            tokens.push(token);
         }
 
-        self.position = self.read_position;
-        self.read_position += 1;
+        let end = Span::new(lexer.position, lexer.position);
+        tokens.push(Token::with_span(TokenType::EOF, end));
+
+        tokens
     }
 
-    /// Gets the next char in the input.
-    fn peak_char(&self) -> Option<char> {
-        if self.is_end_of_file() {
-            return None;
+    /// Reads every token of the input, failing with the errors found if there are any.
+    pub fn tokenize(mut self) -> Result<Vec<Token>, Vec<MonkeyError>> {
+        let tokens = self.read();
+
+        if self.errors.is_empty() {
+            Ok(tokens)
+        } else {
+            Err(self.errors)
         }
+    }
+
+    /// Gets the errors found in the tokens read so far.
+    pub fn errors(&self) -> &[MonkeyError] {
+        &self.errors
+    }
 
-        self.input.chars().nth(self.read_position)
+    /// Reads a char from the input and advances the `read_position` past it.
+    /// `ch` gets set to a null value when we read the end of the input.
+    /// Positions are byte offsets, so they can be used to slice `input`.
+    fn read_char(&mut self) {
+        self.current_character = self.peak_char();
+        self.position = self.read_position;
+        self.read_position += self.current_character.map_or(1, char::len_utf8);
     }
 
-    /// Checks if we are at the end of the `input` given.
-    fn is_end_of_file(&self) -> bool {
-        self.read_position > self.input.len()
+    /// Gets the next char in the input.
+    fn peak_char(&self) -> Option<char> {
+        self.input
+            .get(self.read_position..)
+            .and_then(|rest| rest.chars().next())
     }
 
     /// Reads the `input` for a contiguous string; returning the result.
     fn read_identifier(&mut self) -> &'a str {
         let position = self.position;
 
-        loop {
-            if self.is_end_of_file() {
-                break;
-            }
-
-            if !self.current_character.unwrap().is_alphabetic()
-                || self.current_character.unwrap().is_ascii_whitespace()
-            {
-                break;
-            }
-
+        while self
+            .current_character
+            .is_some_and(|character| character.is_alphabetic())
+        {
             self.read_char();
         }
 
         &self.input[position..self.position]
     }
 
-    /// Reads the `input` for a contiguous integer; returning the value.
-    fn read_digit(&mut self) -> i32 {
+    /// Reads the `input` for a contiguous integer; returning its token.
+    /// Integers too large for an `INT` are reported as errors and read as `ILLEGAL`.
+    fn read_digit(&mut self) -> TokenType {
         let position = self.position;
 
         loop {
-            if !self.current_character.unwrap().is_digit(10) {
+            if !self
+                .current_character
+                .is_some_and(|character| character.is_ascii_digit())
+            {
                 break;
             }
 
             self.read_char();
         }
 
-        self.input[position..self.position].parse().unwrap()
+        let literal = &self.input[position..self.position];
+
+        match literal.parse() {
+            Ok(value) => TokenType::INT(value),
+            Err(_) => {
+                let kind = LexErrorKind::IntegerTooLarge(String::from(literal));
+                let span = Span::new(position, self.position);
+
+                self.errors.push(LexError::new(kind, span).into());
+                TokenType::ILLEGAL
+            }
+        }
     }
 
     /// Matches a string to a Monkey keyword.
@@ -123,79 +218,83 @@ impl<'a> Lexer<'a> {
 
     /// Skips ahead of the `input` until we find non-whitespace characters.
     fn skip_whitespace(&mut self) {
-        loop {
-            if self.current_character.is_none()
-                || !self.current_character.unwrap().is_ascii_whitespace()
-            {
-                return;
-            }
-
+        while self
+            .current_character
+            .is_some_and(|character| character.is_ascii_whitespace())
+        {
             self.read_char();
         }
     }
 
     /// Reads `input` and returns the current token.
     pub fn next_token(&mut self) -> Option<Token> {
+        let whitespace_start = self.position;
+
         self.skip_whitespace();
 
-        if self.current_character.is_none() {
-            return None;
+        if self.trivia && self.position > whitespace_start {
+            return Some(Token::with_span(
+                TokenType::WHITESPACE,
+                Span::new(whitespace_start, self.position),
+            ));
         }
 
-        let token = match self
-            .current_character
-            .expect("Current character is not known.")
-        {
+        let current_character = self.current_character?;
+        let start = self.position;
+        let t_type = match current_character {
             '=' => match self.peak_char() {
-                Some(x) => {
-                    if x == '=' {
-                        self.read_char();
-
-                        Token::new(TokenType::EQ)
-                    } else {
-                        Token::new(TokenType::ASSIGN)
-                    }
+                Some('=') => {
+                    self.read_char();
+
+                    TokenType::EQ
                 }
-                None => Token::new(TokenType::ILLEGAL),
+                _ => TokenType::ASSIGN,
             },
-            ';' => Token::new(TokenType::SEMICOLON),
-            '(' => Token::new(TokenType::LPAREN),
-            ')' => Token::new(TokenType::RPAREN),
-            '{' => Token::new(TokenType::LBRACE),
-            '}' => Token::new(TokenType::RBRACE),
-            ',' => Token::new(TokenType::COMMA),
-            '+' => Token::new(TokenType::PLUS),
-            '-' => Token::new(TokenType::MINUS),
-            '*' => Token::new(TokenType::ASTERISK),
-            '/' => Token::new(TokenType::FORWARDSLASH),
+            ';' => TokenType::SEMICOLON,
+            '(' => TokenType::LPAREN,
+            ')' => TokenType::RPAREN,
+            '{' => TokenType::LBRACE,
+            '}' => TokenType::RBRACE,
+            ',' => TokenType::COMMA,
+            '+' => TokenType::PLUS,
+            '-' => TokenType::MINUS,
+            '*' => TokenType::ASTERISK,
+            '/' => TokenType::FORWARDSLASH,
             '!' => match self.peak_char() {
-                Some(x) => {
-                    if x == '=' {
-                        self.read_char();
-
-                        Token::new(TokenType::NOTEQ)
-                    } else {
-                        Token::new(TokenType::BANG)
-                    }
+                Some('=') => {
+                    self.read_char();
+
+                    TokenType::NOTEQ
                 }
-                None => Token::new(TokenType::ILLEGAL),
+                _ => TokenType::BANG,
             },
-            '<' => Token::new(TokenType::LT),
-            '>' => Token::new(TokenType::GT),
+            '<' => TokenType::LT,
+            '>' => TokenType::GT,
+            // Identifiers and integers already advance past their last character,
+            // so they return before the trailing `read_char` below.
             item => {
                 if item.is_alphabetic() {
-                    Token::new(Lexer::lookup_identifier(self.read_identifier()))
-                } else if item.is_digit(10) {
-                    Token::new(TokenType::INT(self.read_digit()))
+                    let t_type = Lexer::lookup_identifier(self.read_identifier());
+
+                    return Some(Token::with_span(t_type, Span::new(start, self.position)));
+                } else if item.is_ascii_digit() {
+                    let t_type = self.read_digit();
+
+                    return Some(Token::with_span(t_type, Span::new(start, self.position)));
                 } else {
-                    Token::new(TokenType::ILLEGAL)
+                    let kind = LexErrorKind::IllegalCharacter(item);
+                    let span = Span::new(start, self.read_position);
+
+                    self.errors.push(LexError::new(kind, span).into());
+                    TokenType::ILLEGAL
                 }
             }
         };
 
+        let span = Span::new(start, self.read_position);
         self.read_char();
 
-        Some(token)
+        Some(Token::with_span(t_type, span))
     }
 }
 
@@ -214,15 +313,11 @@ fn it_should_read_chars() {
 fn it_should_lex_double_tokens() {
     // Arrange
     let input = "== !=";
-    let expected = vec![
-        Token::new(TokenType::EQ),
-        Token::new(TokenType::NOTEQ),
-        Token::new(TokenType::EOF),
-    ];
+    let expected = vec![TokenType::EQ, TokenType::NOTEQ, TokenType::EOF];
 
     // Act
     let mut lexer = Lexer::new(input);
-    let tokens = lexer.read();
+    let tokens: Vec<TokenType> = lexer.read().into_iter().map(|token| token.t_type).collect();
 
     // Assert
     assert_eq!(tokens, expected);
@@ -233,19 +328,19 @@ fn it_should_lex_keywords_tokens() {
     // Arrange
     let input = "fn let true false if else return";
     let expected = vec![
-        Token::new(TokenType::FUNCTION),
-        Token::new(TokenType::LET),
-        Token::new(TokenType::TRUE),
-        Token::new(TokenType::FALSE),
-        Token::new(TokenType::IF),
-        Token::new(TokenType::ELSE),
-        Token::new(TokenType::RETURN),
-        Token::new(TokenType::EOF),
+        TokenType::FUNCTION,
+        TokenType::LET,
+        TokenType::TRUE,
+        TokenType::FALSE,
+        TokenType::IF,
+        TokenType::ELSE,
+        TokenType::RETURN,
+        TokenType::EOF,
     ];
 
     // Act
     let mut lexer = Lexer::new(input);
-    let tokens = lexer.read();
+    let tokens: Vec<TokenType> = lexer.read().into_iter().map(|token| token.t_type).collect();
 
     // Assert
     assert_eq!(tokens, expected);
@@ -256,21 +351,151 @@ fn it_should_lex_single_tokens() {
     // Arrange
     let input = "=+-*/!<>,;(){}";
     let expected = vec![
-        Token::new(TokenType::ASSIGN),
-        Token::new(TokenType::PLUS),
-        Token::new(TokenType::MINUS),
-        Token::new(TokenType::ASTERISK),
-        Token::new(TokenType::FORWARDSLASH),
-        Token::new(TokenType::BANG),
-        Token::new(TokenType::LT),
-        Token::new(TokenType::GT),
-        Token::new(TokenType::COMMA),
-        Token::new(TokenType::SEMICOLON),
-        Token::new(TokenType::LPAREN),
-        Token::new(TokenType::RPAREN),
-        Token::new(TokenType::LBRACE),
-        Token::new(TokenType::RBRACE),
-        Token::new(TokenType::EOF),
+        TokenType::ASSIGN,
+        TokenType::PLUS,
+        TokenType::MINUS,
+        TokenType::ASTERISK,
+        TokenType::FORWARDSLASH,
+        TokenType::BANG,
+        TokenType::LT,
+        TokenType::GT,
+        TokenType::COMMA,
+        TokenType::SEMICOLON,
+        TokenType::LPAREN,
+        TokenType::RPAREN,
+        TokenType::LBRACE,
+        TokenType::RBRACE,
+        TokenType::EOF,
+    ];
+
+    // Act
+    let mut lexer = Lexer::new(input);
+    let tokens: Vec<TokenType> = lexer.read().into_iter().map(|token| token.t_type).collect();
+
+    // Assert
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn it_should_lex_tokens_after_identifiers_and_integers() {
+    // Arrange
+    let input = "add(x, 5);";
+    let expected = vec![
+        TokenType::IDENT(String::from("add")),
+        TokenType::LPAREN,
+        TokenType::IDENT(String::from("x")),
+        TokenType::COMMA,
+        TokenType::INT(5),
+        TokenType::RPAREN,
+        TokenType::SEMICOLON,
+        TokenType::EOF,
+    ];
+
+    // Act
+    let mut lexer = Lexer::new(input);
+    let tokens: Vec<TokenType> = lexer.read().into_iter().map(|token| token.t_type).collect();
+
+    // Assert
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn it_should_record_token_spans() {
+    // Arrange
+    let input = "let five = 5;";
+    let expected = vec![
+        Span::new(0, 3),
+        Span::new(4, 8),
+        Span::new(9, 10),
+        Span::new(11, 12),
+        Span::new(12, 13),
+        Span::new(13, 13),
+    ];
+
+    // Act
+    let mut lexer = Lexer::new(input);
+    let spans: Vec<Span> = lexer.read().into_iter().map(|token| token.span).collect();
+
+    // Assert
+    assert_eq!(spans, expected);
+}
+
+#[test]
+fn it_should_lex_an_integer_at_the_end_of_input() {
+    // Arrange
+    let input = "1 + 2";
+    let expected = vec![
+        TokenType::INT(1),
+        TokenType::PLUS,
+        TokenType::INT(2),
+        TokenType::EOF,
+    ];
+
+    // Act
+    let mut lexer = Lexer::new(input);
+    let tokens: Vec<TokenType> = lexer.read().into_iter().map(|token| token.t_type).collect();
+
+    // Assert
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn it_should_report_errors() {
+    // Arrange
+    let input = "let a = 5 $ 99999999999;";
+    let expected = vec![
+        MonkeyError::from(LexError::new(
+            LexErrorKind::IllegalCharacter('$'),
+            Span::new(10, 11),
+        )),
+        MonkeyError::from(LexError::new(
+            LexErrorKind::IntegerTooLarge(String::from("99999999999")),
+            Span::new(12, 23),
+        )),
+    ];
+
+    // Act
+    let mut lexer = Lexer::new(input);
+    let tokens: Vec<TokenType> = lexer.read().into_iter().map(|token| token.t_type).collect();
+
+    // Assert
+    assert_eq!(tokens[4], TokenType::ILLEGAL);
+    assert_eq!(tokens[5], TokenType::ILLEGAL);
+    assert_eq!(lexer.errors(), expected.as_slice());
+}
+
+#[test]
+fn it_should_lex_operators_at_the_end_of_input() {
+    // Arrange
+    let input = "a = !";
+    let expected = vec![
+        TokenType::IDENT(String::from("a")),
+        TokenType::ASSIGN,
+        TokenType::BANG,
+        TokenType::EOF,
+    ];
+
+    // Act
+    let mut lexer = Lexer::new(input);
+    let tokens: Vec<TokenType> = lexer.read().into_iter().map(|token| token.t_type).collect();
+
+    // Assert
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn it_should_lex_non_ascii_input() {
+    // Arrange
+    let input = "let café = \"☃\";";
+    let expected = vec![
+        Token::with_span(TokenType::LET, Span::new(0, 3)),
+        Token::with_span(TokenType::IDENT(String::from("café")), Span::new(4, 9)),
+        Token::with_span(TokenType::ASSIGN, Span::new(10, 11)),
+        Token::with_span(TokenType::ILLEGAL, Span::new(12, 13)),
+        Token::with_span(TokenType::ILLEGAL, Span::new(13, 16)),
+        Token::with_span(TokenType::ILLEGAL, Span::new(16, 17)),
+        Token::with_span(TokenType::SEMICOLON, Span::new(17, 18)),
+        Token::with_span(TokenType::EOF, Span::new(18, 18)),
     ];
 
     // Act
@@ -280,3 +505,77 @@ fn it_should_lex_single_tokens() {
     // Assert
     assert_eq!(tokens, expected);
 }
+
+#[test]
+fn it_should_relex_only_around_an_edit() {
+    // Arrange
+    let before = "let five = 5;\nlet ten = 10;";
+    let after = "let five = 55;\nlet ten = 10;";
+    let previous = Lexer::new(before).read();
+
+    // Act
+    let tokens = Lexer::relex(after, 11..12, "55", &previous);
+
+    // Assert
+    assert_eq!(tokens, Lexer::new(after).read());
+    assert_eq!(tokens[3].t_type, TokenType::INT(55));
+    assert_eq!(tokens[8].span, Span::new(25, 27));
+}
+
+#[test]
+fn it_should_relex_the_same_as_lexing_again() {
+    // Arrange
+    let before = "let add = fn(x, y) { x + y; }; add(5 != 10)";
+    let edits = ["", "=", "a", "5", " ", "ten", "!"];
+    let previous = Lexer::new(before).read();
+
+    for start in 0..=before.len() {
+        for end in start..=before.len().min(start + 3) {
+            for new_text in edits {
+                let after = format!("{}{}{}", &before[..start], new_text, &before[end..]);
+
+                // Act
+                let tokens = Lexer::relex(&after, start..end, new_text, &previous);
+
+                // Assert
+                assert_eq!(tokens, Lexer::new(&after).read(), "editing {:?}", after);
+            }
+        }
+    }
+}
+
+#[test]
+fn it_should_lex_whitespace_as_trivia() {
+    // Arrange
+    let input = "let a =\n\t5; ";
+    let expected = vec![
+        TokenType::LET,
+        TokenType::WHITESPACE,
+        TokenType::IDENT(String::from("a")),
+        TokenType::WHITESPACE,
+        TokenType::ASSIGN,
+        TokenType::WHITESPACE,
+        TokenType::INT(5),
+        TokenType::SEMICOLON,
+        TokenType::WHITESPACE,
+        TokenType::EOF,
+    ];
+
+    // Act
+    let mut lexer = Lexer::new(input).with_trivia();
+    let tokens = lexer.read();
+    let text: String = tokens
+        .iter()
+        .map(|token| &input[token.span.start..token.span.end])
+        .collect();
+
+    // Assert
+    assert_eq!(
+        tokens
+            .into_iter()
+            .map(|token| token.t_type)
+            .collect::<Vec<_>>(),
+        expected
+    );
+    assert_eq!(text, input);
+}
@@ -0,0 +1,26 @@
+//! An interpreter for the Monkey programming language, following
+//! [Writing An Interpreter In Go](https://interpreterbook.com).
+//!
+//! The pipeline currently ends at the lexer: [`Lexer`] turns source into [`Token`]s,
+//! reporting anything it couldn't recognize as a [`MonkeyError`].
+//!
+//! ```
+//! use monkeylang::{Lexer, TokenType};
+//!
+//! let tokens = Lexer::new("let five = 5;").read();
+//!
+//! assert_eq!(tokens[0].t_type, TokenType::LET);
+//! ```
+
+pub mod diagnostics;
+pub mod error;
+pub mod lexer;
+pub mod source_map;
+pub mod timing;
+pub mod token;
+
+pub use diagnostics::Diagnostic;
+pub use error::MonkeyError;
+pub use lexer::Lexer;
+pub use source_map::SourceMap;
+pub use token::{Span, Token, TokenType};
@@ -0,0 +1,93 @@
+/// Identifies a source registered in a [`SourceMap`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct FileId(usize);
+
+#[derive(Debug)]
+struct SourceFile {
+    name: String,
+    source: String,
+}
+
+/// Where an offset in a source is, in terms people can read.
+#[derive(Debug, PartialEq)]
+pub struct Location<'a> {
+    pub file: &'a str,
+    /// The line, starting at 1.
+    pub line: usize,
+    /// The character within the line, starting at 1.
+    pub column: usize,
+}
+
+/// Keeps every source the pipeline has seen, whether read from a file, typed into the REPL,
+/// or passed with `-e`, so spans can be traced back to where they came from.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap::default()
+    }
+
+    /// Registers `source` under `name`, like a path or `<repl:1>`.
+    pub fn add(&mut self, name: impl Into<String>, source: impl Into<String>) -> FileId {
+        self.files.push(SourceFile {
+            name: name.into(),
+            source: source.into(),
+        });
+
+        FileId(self.files.len() - 1)
+    }
+
+    pub fn name(&self, file: FileId) -> &str {
+        &self.files[file.0].name
+    }
+
+    pub fn source(&self, file: FileId) -> &str {
+        &self.files[file.0].source
+    }
+
+    /// Finds the line and column of a byte `offset` into `file`.
+    pub fn location(&self, file: FileId, offset: usize) -> Location<'_> {
+        let source = self.source(file);
+        let offset = offset.min(source.len());
+        let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+
+        Location {
+            file: self.name(file),
+            line: source[..offset].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+        }
+    }
+}
+
+#[test]
+fn it_should_locate_offsets_in_each_file() {
+    // Arrange
+    let mut sources = SourceMap::new();
+    let main = sources.add("main.mky", "let five = 5;\nlet ten = 10;");
+    let repl = sources.add("<repl:1>", "five + ten");
+
+    // Act
+    let in_main = sources.location(main, 18);
+    let in_repl = sources.location(repl, 7);
+
+    // Assert
+    assert_eq!(
+        in_main,
+        Location {
+            file: "main.mky",
+            line: 2,
+            column: 5
+        }
+    );
+    assert_eq!(
+        in_repl,
+        Location {
+            file: "<repl:1>",
+            line: 1,
+            column: 8
+        }
+    );
+}
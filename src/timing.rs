@@ -0,0 +1,59 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How long each phase of the pipeline took for one piece of source,
+/// along with counts of what the phases produced.
+#[derive(Debug, Default)]
+pub struct Timings {
+    phases: Vec<(&'static str, Duration)>,
+    counts: Vec<(&'static str, usize)>,
+}
+
+impl Timings {
+    /// Runs `phase`, recording how long it took under `name`.
+    pub fn measure<T>(&mut self, name: &'static str, phase: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = phase();
+
+        self.phases.push((name, start.elapsed()));
+
+        result
+    }
+
+    /// Records how many of `name` a phase produced, like the number of tokens.
+    pub fn count(&mut self, name: &'static str, value: usize) {
+        self.counts.push((name, value));
+    }
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let phases = self
+            .phases
+            .iter()
+            .map(|(name, duration)| format!("{:<8}{:?}", name, duration));
+        let counts = self
+            .counts
+            .iter()
+            .map(|(name, value)| format!("{:<8}{}", name, value));
+        let lines: Vec<String> = phases.chain(counts).collect();
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[test]
+fn it_should_record_each_phase() {
+    // Arrange
+    let mut timings = Timings::default();
+
+    // Act
+    let value = timings.measure("lex", || 5);
+    timings.count("tokens", 12);
+
+    // Assert
+    assert_eq!(value, 5);
+    assert_eq!(timings.phases.len(), 1);
+    assert!(timings.to_string().starts_with("lex     "));
+    assert!(timings.to_string().ends_with("\ntokens  12"));
+}
@@ -1,8 +1,11 @@
-#[derive(Debug, PartialEq)]
+/// The kinds of token in Monkey.
+#[derive(Debug, PartialEq, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum TokenType {
-    #[allow(dead_code)]
     EOF,
     ILLEGAL,
+    /// A run of whitespace; only produced by [`Lexer::with_trivia`](crate::Lexer::with_trivia).
+    WHITESPACE,
 
     IDENT(String),
     INT(i32),
@@ -36,15 +39,35 @@ pub enum TokenType {
     RETURN,
 }
 
-#[derive(Debug, PartialEq)]
-#[allow(dead_code)]
+/// A range of byte offsets into the source, from `start` up to (but excluding) `end`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+}
+
+/// A token read from Monkey source.
+#[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     pub t_type: TokenType,
+    /// Where the token was read from in the source.
+    pub span: Span,
 }
 
 impl Token {
     pub fn new(t: TokenType) -> Self {
-        Token { t_type: t }
+        Token::with_span(t, Span::default())
+    }
+
+    /// Creates a token which knows where it was read from.
+    pub fn with_span(t: TokenType, span: Span) -> Self {
+        Token { t_type: t, span }
     }
 }
 
@@ -0,0 +1,91 @@
+//! Runs the `monkey` binary over every `.mky` program under `testdata/`, once per stage of the
+//! pipeline, and compares what it printed with the `<name>.<stage>.expected` file next to it.
+//!
+//! Lexing is the only stage so far. After changing what a stage prints, regenerate the
+//! expected files with `MONKEY_BLESS=1 cargo test --test golden` and review the diff.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Every stage compared against expected output: the name used in its file extension,
+/// and the arguments which make `monkey` print it, before the program's file name.
+const STAGES: [(&str, &[&str]); 1] = [("tokens", &["run", "--emit", "tokens"])];
+
+/// Gets the directory holding the programs and their expected output.
+fn testdata() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata")
+}
+
+/// Runs `monkey` with `args` from `testdata/`, so diagnostics name files as they're listed there.
+/// Returns stdout, then stderr and the exit status, as one text to compare.
+fn run_monkey(args: &[&str], name: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_monkey"))
+        .args(args)
+        .arg(name)
+        .current_dir(testdata())
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("the monkey binary should run");
+
+    format!(
+        "{}--- stderr\n{}--- exit status: {}\n",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr),
+        output.status.code().unwrap_or(-1)
+    )
+}
+
+/// Gets every `.mky` program under `testdata/`, in a stable order.
+fn programs() -> Vec<PathBuf> {
+    let mut programs: Vec<PathBuf> = fs::read_dir(testdata())
+        .expect("testdata/ should exist")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "mky"))
+        .collect();
+
+    programs.sort();
+    programs
+}
+
+#[test]
+fn it_should_match_the_expected_output_of_each_program() {
+    // Arrange
+    let bless = env::var_os("MONKEY_BLESS").is_some();
+    let mut mismatches = vec![];
+
+    for program in programs() {
+        let name = program.file_name().unwrap().to_string_lossy();
+
+        for (stage, args) in STAGES {
+            let expected_path = program.with_extension(format!("{}.expected", stage));
+
+            // Act
+            let actual = run_monkey(args, &name);
+
+            // Assert
+            if bless {
+                fs::write(&expected_path, &actual).unwrap();
+                continue;
+            }
+
+            match fs::read_to_string(&expected_path) {
+                Ok(expected) if expected == actual => {}
+                Ok(expected) => mismatches.push(format!(
+                    "{}:\n--- expected\n{}\n+++ actual\n{}",
+                    expected_path.display(),
+                    expected,
+                    actual
+                )),
+                Err(_) => mismatches.push(format!("{}: missing", expected_path.display())),
+            }
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "{}\n\nRun `MONKEY_BLESS=1 cargo test --test golden` to accept the new output.",
+        mismatches.join("\n\n")
+    );
+}